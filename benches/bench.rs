@@ -31,6 +31,30 @@ fn bench_byteio(b: &mut test::Bencher) {
     b.bytes = 2 * NITER as u64;
 }
 
+#[bench]
+fn bench_byteio_read_into(b: &mut test::Bencher) {
+    use byteio::ReadBytesExt;
+    let vec = vec![0u8; 1_000_000];
+    let mut dst = vec![0u16; 500_000];
+    b.iter(|| {
+        let mut data = black_box(&vec[..]);
+        black_box(data.read_into::<byteio::LittleEndian>(&mut dst)).unwrap();
+    });
+    b.bytes = vec.len() as u64;
+}
+
+#[bench]
+fn bench_byteio_write_slice(b: &mut test::Bencher) {
+    use byteio::WriteBytesExt;
+    let mut src = vec![0u16; 500_000];
+    let mut buf = Vec::with_capacity(1_000_000);
+    b.iter(|| {
+        buf.clear();
+        black_box(buf.write_slice::<byteio::LittleEndian>(&mut src)).unwrap();
+    });
+    b.bytes = 1_000_000;
+}
+
 #[bench]
 fn bench_byteorder_vec(b: &mut test::Bencher) {
     use byteorder::ReadBytesExt;
@@ -0,0 +1,129 @@
+//! An endianness-carrying value wrapper that keeps its bytes in a fixed
+//! byte order `B`, so that operations which don't care about byte order
+//! (masking, flag manipulation) can run directly on the serialized bytes.
+
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, BitXor};
+
+use super::ByteOrder;
+use super::sealed::Scalar;
+
+/// A value of type `T`, stored byte-swapped into byte order `B`.
+///
+/// Since `BitAnd`, `BitOr` and `BitXor` are endianness-agnostic (they act
+/// independently on each byte), they are implemented directly on the
+/// stored bytes, letting callers mask or combine values that are parsed
+/// straight out of a protocol header without swapping them back to native
+/// order first.
+#[derive(Clone, Copy)]
+pub struct Endian<T: Scalar, B: ByteOrder<T>> {
+    bytes: B::Buffer,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Scalar, B: ByteOrder<T>> Endian<T, B> {
+    /// Decodes the stored bytes into a value in native byte order.
+    pub fn get(self) -> T {
+        B::from_bytes(self.bytes)
+    }
+}
+
+impl<T: Scalar, B: ByteOrder<T>> From<T> for Endian<T, B> {
+    fn from(value: T) -> Self {
+        Endian { bytes: B::into_bytes(value), _marker: PhantomData }
+    }
+}
+
+impl<T: Scalar, B: ByteOrder<T>> PartialEq for Endian<T, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes.as_ref() == other.bytes.as_ref()
+    }
+}
+
+impl<T: Scalar, B: ByteOrder<T>> Eq for Endian<T, B> {}
+
+impl<T: Scalar, B: ByteOrder<T>> BitAnd for Endian<T, B> {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        for (a, b) in self.bytes.as_mut().iter_mut().zip(rhs.bytes.as_ref().iter()) {
+            *a &= *b;
+        }
+        self
+    }
+}
+
+impl<T: Scalar, B: ByteOrder<T>> BitOr for Endian<T, B> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        for (a, b) in self.bytes.as_mut().iter_mut().zip(rhs.bytes.as_ref().iter()) {
+            *a |= *b;
+        }
+        self
+    }
+}
+
+impl<T: Scalar, B: ByteOrder<T>> BitXor for Endian<T, B> {
+    type Output = Self;
+
+    fn bitxor(mut self, rhs: Self) -> Self {
+        for (a, b) in self.bytes.as_mut().iter_mut().zip(rhs.bytes.as_ref().iter()) {
+            *a ^= *b;
+        }
+        self
+    }
+}
+
+macro_rules! impl_into {
+    ($val:ty) => {
+        impl<B: ByteOrder<$val>> From<Endian<$val, B>> for $val {
+            fn from(wrapped: Endian<$val, B>) -> $val {
+                wrapped.get()
+            }
+        }
+    }
+}
+
+impl_into!(u8);
+impl_into!(u16);
+impl_into!(u32);
+impl_into!(u64);
+impl_into!(i8);
+impl_into!(i16);
+impl_into!(i32);
+impl_into!(i64);
+impl_into!(f32);
+impl_into!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::Endian;
+    use {BigEndian, LittleEndian};
+
+    #[test]
+    fn bitand_or_xor_operate_on_serialized_bytes() {
+        let a: Endian<u16, BigEndian> = Endian::from(0x0f0f);
+        let b: Endian<u16, BigEndian> = Endian::from(0x00ff);
+        assert_eq!(u16::from(a & b), 0x000f);
+        assert_eq!(u16::from(a | b), 0x0fff);
+        assert_eq!(u16::from(a ^ b), 0x0ff0);
+    }
+
+    #[test]
+    fn bitwise_ops_do_not_move_their_operands() {
+        let a: Endian<u16, LittleEndian> = Endian::from(0xff00);
+        let b: Endian<u16, LittleEndian> = Endian::from(0x00ff);
+        let _ = a & b;
+        // `a` and `b` are still usable here because `Endian` is `Copy`.
+        assert_eq!(u16::from(a | b), 0xffff);
+    }
+
+    #[test]
+    fn eq_compares_serialized_bytes() {
+        let a: Endian<u16, BigEndian> = Endian::from(1u16);
+        let b: Endian<u16, LittleEndian> = Endian::from(1u16);
+        assert!(a != Endian::from(2u16));
+        let _ = b;
+    }
+}
@@ -13,43 +13,64 @@
 
 use std::io;
 use std::mem;
+use std::slice;
 
-/// Type that can easily be converted to a (mutable) reference to a slice.
-///
-/// ## Note
-/// This is part of a workaround until associated constants are stable
-pub trait AsSlice<T>: AsRef<[T]> + AsMut<[T]> {}
-impl<T, V> AsSlice<T> for V where V: AsRef<[T]> + AsMut<[T]> {}
+mod unaligned;
+pub use unaligned::{U16, U32, U64, I16, I32, I64};
+
+mod endian;
+pub use endian::Endian;
+
+mod sealed {
+    /// Marker for the plain, fixed-size numeric types this crate converts.
+    ///
+    /// This is private on purpose: `read_into`/`write_slice` reinterpret a
+    /// `&mut [T]` as raw bytes, which is only sound for types with no
+    /// padding and no invalid bit patterns. Keeping `Scalar` unnameable
+    /// outside the crate stops a downstream crate from implementing
+    /// `ByteOrder` for its own (possibly unsound-to-reinterpret) types.
+    pub trait Scalar {}
+}
 
 /// Conversion of a (small) type to a byte array and vice versa.
 ///
-/// ## Note 
+/// ## Note
 /// This should not be used to convert big types to byte arrays as it will
 /// overflow the stack.
-pub trait ByteOrder<T> {
-    /// Conversion buffer type.
-    ///
-    /// Should be big enough to hold a T.
-    ///
-    /// ## Note
-    /// This is a workaround until associated constants are stable
-    type Buffer: AsSlice<u8>;
+pub trait ByteOrder<T: sealed::Scalar> {
+    /// Number of bytes used to represent a `T` in this byte order.
+    const SIZE: usize;
+    /// Conversion buffer type, a `[u8; Self::SIZE]` array.
+    type Buffer: AsRef<[u8]> + AsMut<[u8]> + Default + Copy;
     /// Converts the byte array `buf` into a `T`.
     fn from_bytes(buf: Self::Buffer) -> T;
     /// Converts `n` into a byte array.
     fn into_bytes(n: T) -> Self::Buffer;
-    /// Returns a sufficiently big conversion buffer buffer.
-    /// 
-    /// ## Note
-    /// This is a workaround until associated constants are stable
-    fn buffer() -> Self::Buffer;
+    /// Byte-swaps every element of `slice` between native order and this
+    /// byte order, in place.
+    ///
+    /// On targets where this byte order already matches the native one,
+    /// swapping is a no-op per element.
+    fn swap_slice(slice: &mut [T]);
+    /// Decodes `slice` from this byte order into native order, in place.
+    #[inline]
+    fn from_slice(slice: &mut [T]) {
+        Self::swap_slice(slice)
+    }
+    /// Encodes `slice` from native order into this byte order, in place.
+    #[inline]
+    fn to_slice(slice: &mut [T]) {
+        Self::swap_slice(slice)
+    }
 }
 
 /// Little endian byte order.
+#[derive(Clone, Copy)]
 pub enum LittleEndian {}
 /// Little endian byte order.
 pub type LE = LittleEndian;
 /// Big endian byte order.
+#[derive(Clone, Copy)]
 pub enum BigEndian {}
 /// Big endian byte order.
 pub type BE = BigEndian;
@@ -63,102 +84,138 @@ pub type NetworkByteOrder = LittleEndian;
 #[cfg(target_endian = "big")]
 pub type NativeByteOrder = BigEndian;
 
+macro_rules! impl_scalar {
+    ($($val:ty),*) => {
+        $(impl sealed::Scalar for $val {})*
+    }
+}
+
+impl_scalar!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
 macro_rules! impl_byte_order {
-    ($val:ident, $bytes:expr, $byte_order:ident, $convert:ident) => {
+    ($val:ident, $bytes:expr, $byte_order:ident, $from_bytes:ident, $to_bytes:ident, $convert:ident) => {
         impl ByteOrder<$val> for $byte_order {
+            const SIZE: usize = $bytes;
             type Buffer = [u8; $bytes];
-    
+
             #[inline]
             fn from_bytes(buf: Self::Buffer) -> $val {
-                unsafe { mem::transmute::<_, $val>(buf) }.$convert()
-                
+                $val::$from_bytes(buf)
             }
-            
+
             #[inline]
             fn into_bytes(n: $val) -> Self::Buffer {
-                unsafe { mem::transmute(n.$convert()) }
+                n.$to_bytes()
             }
-            
+
             #[inline]
-            fn buffer() -> Self::Buffer {
-                [0; $bytes]
+            fn swap_slice(slice: &mut [$val]) {
+                for x in slice.iter_mut() {
+                    *x = x.$convert();
+                }
             }
         }
     };
-    ($byte_order:ident, $convert:ident) => {
-        impl_byte_order!(u8 , 1, $byte_order, $convert);
-        impl_byte_order!(u16, 2, $byte_order, $convert);
-        impl_byte_order!(u32, 4, $byte_order, $convert);
-        impl_byte_order!(u64, 8, $byte_order, $convert);
-        impl_byte_order!(i8 , 1, $byte_order, $convert);
-        impl_byte_order!(i16, 2, $byte_order, $convert);
-        impl_byte_order!(i32, 4, $byte_order, $convert);
-        impl_byte_order!(i64, 8, $byte_order, $convert);
-        
+    ($byte_order:ident, $from_bytes:ident, $to_bytes:ident, $convert:ident) => {
+        impl_byte_order!(u8 , 1, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(u16, 2, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(u32, 4, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(u64, 8, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(i8 , 1, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(i16, 2, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(i32, 4, $byte_order, $from_bytes, $to_bytes, $convert);
+        impl_byte_order!(i64, 8, $byte_order, $from_bytes, $to_bytes, $convert);
+
         impl ByteOrder<f32> for $byte_order {
+            const SIZE: usize = 4;
             type Buffer = [u8; 4];
-    
+
             #[inline]
             fn from_bytes(buf: Self::Buffer) -> f32 {
-                unsafe {
-                    mem::transmute(mem::transmute::<_, u32>(buf).$convert())
-                }
+                f32::from_bits(u32::$from_bytes(buf))
             }
-            
+
             #[inline]
             fn into_bytes(n: f32) -> Self::Buffer {
-                unsafe { 
-                    mem::transmute(mem::transmute::<_, u32>(n).$convert())
-                }
+                n.to_bits().$to_bytes()
             }
-            
+
             #[inline]
-            fn buffer() -> Self::Buffer {
-                [0; 4]
+            fn swap_slice(slice: &mut [f32]) {
+                for x in slice.iter_mut() {
+                    *x = f32::from_bits(x.to_bits().$convert());
+                }
             }
         }
-        
+
         impl ByteOrder<f64> for $byte_order {
+            const SIZE: usize = 8;
             type Buffer = [u8; 8];
-    
+
             #[inline]
             fn from_bytes(buf: Self::Buffer) -> f64 {
-                unsafe {
-                    mem::transmute(mem::transmute::<_, u64>(buf).$convert())
-                }
+                f64::from_bits(u64::$from_bytes(buf))
             }
-            
+
             #[inline]
             fn into_bytes(n: f64) -> Self::Buffer {
-                unsafe { 
-                    mem::transmute(mem::transmute::<_, u64>(n).$convert())
-                }
+                n.to_bits().$to_bytes()
             }
-            
+
             #[inline]
-            fn buffer() -> Self::Buffer {
-                [0; 8]
+            fn swap_slice(slice: &mut [f64]) {
+                for x in slice.iter_mut() {
+                    *x = f64::from_bits(x.to_bits().$convert());
+                }
             }
         }
     }
 }
 
-impl_byte_order!(LittleEndian, to_le);
-impl_byte_order!(BigEndian, to_be);
+impl_byte_order!(LittleEndian, from_le_bytes, to_le_bytes, to_le);
+impl_byte_order!(BigEndian, from_be_bytes, to_be_bytes, to_be);
+
+/// Reinterprets `slice` as a mutable byte slice covering the same memory.
+///
+/// ## Safety
+/// `T: sealed::Scalar` is only implemented for this crate's own fixed-size
+/// numeric types, none of which have padding or invalid bit patterns, so
+/// this reinterpretation is sound for every `T` that can reach it. The
+/// bound is what keeps a downstream crate from calling this through its
+/// own `ByteOrder` impl for an unsound-to-reinterpret type.
+#[inline]
+unsafe fn as_bytes_mut<T: sealed::Scalar>(slice: &mut [T]) -> &mut [u8] {
+    slice::from_raw_parts_mut(
+        slice.as_mut_ptr() as *mut u8,
+        slice.len() * mem::size_of::<T>()
+    )
+}
 
 /// Extension trait for `io::Read` that allows to read `T`s from it.
-pub trait ReadBytesExt<T> {
+pub trait ReadBytesExt<T: sealed::Scalar> {
     fn read_as<B: ByteOrder<T>>(&mut self) -> io::Result<T>;
+    /// Fills `dst` by reading `dst.len()` consecutive `T`s in byte order `B`,
+    /// with one `read_exact` call straight into `dst`'s memory followed by
+    /// an in-place byte swap.
+    fn read_into<B: ByteOrder<T>>(&mut self, dst: &mut [T]) -> io::Result<()>;
 }
 /// Extension trait for `io::Write` that allows to write `T`s from it.
-pub trait WriteBytesExt<T> {
+pub trait WriteBytesExt<T: sealed::Scalar> {
     fn write_as<B: ByteOrder<T>>(&mut self, n: T) -> io::Result<()>;
+    /// Writes all of `src` in byte order `B`, with one `write_all` call over
+    /// `src`'s memory.
+    ///
+    /// `src` is byte-swapped into `B` in place before writing and swapped
+    /// back to its original, native-order contents afterwards, so no
+    /// separate buffer is allocated. On targets where `B` already matches
+    /// the native byte order the swap is a no-op per element.
+    fn write_slice<B: ByteOrder<T>>(&mut self, src: &mut [T]) -> io::Result<()>;
 }
 
-impl<T, R: io::Read> ReadBytesExt<T> for R {
+impl<T: sealed::Scalar, R: io::Read> ReadBytesExt<T> for R {
     #[inline]
     fn read_as<B: ByteOrder<T>>(&mut self) -> io::Result<T> {
-        let mut buf = B::buffer();
+        let mut buf = B::Buffer::default();
         if try!(self.read(buf.as_mut())) != buf.as_ref().len() {
             return Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -167,12 +224,236 @@ impl<T, R: io::Read> ReadBytesExt<T> for R {
         }
         Ok(B::from_bytes(buf))
     }
+
+    #[inline]
+    fn read_into<B: ByteOrder<T>>(&mut self, dst: &mut [T]) -> io::Result<()> {
+        try!(self.read_exact(unsafe { as_bytes_mut(dst) }));
+        B::from_slice(dst);
+        Ok(())
+    }
 }
 
-impl<T, W: io::Write> WriteBytesExt<T> for W {
+impl<T: sealed::Scalar, W: io::Write> WriteBytesExt<T> for W {
     #[inline]
     fn write_as<B: ByteOrder<T>>(&mut self, n: T) -> io::Result<()> {
         let buf = B::into_bytes(n);
         self.write_all(buf.as_ref())
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn write_slice<B: ByteOrder<T>>(&mut self, src: &mut [T]) -> io::Result<()> {
+        B::to_slice(src);
+        let result = self.write_all(unsafe { as_bytes_mut(src) });
+        B::from_slice(src);
+        result
+    }
+}
+
+#[cfg(test)]
+mod slice_tests {
+    use {BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+
+    #[test]
+    fn read_into_round_trips_write_slice() {
+        let mut src = [1u16, 2, 3, 0x0102];
+        let mut buf = Vec::new();
+        buf.write_slice::<BigEndian>(&mut src).unwrap();
+        // `write_slice` must leave `src` in its original, native-order state.
+        assert_eq!(src, [1u16, 2, 3, 0x0102]);
+
+        let mut dst = [0u16; 4];
+        (&buf[..]).read_into::<BigEndian>(&mut dst).unwrap();
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn write_slice_matches_byte_order_on_the_wire() {
+        let mut src = [0x0102u16];
+        let mut buf = Vec::new();
+        buf.write_slice::<BigEndian>(&mut src).unwrap();
+        assert_eq!(buf, [0x01, 0x02]);
+
+        let mut src = [0x0102u16];
+        let mut buf = Vec::new();
+        buf.write_slice::<LittleEndian>(&mut src).unwrap();
+        assert_eq!(buf, [0x02, 0x01]);
+    }
+
+    #[test]
+    fn read_into_errors_on_short_source() {
+        let data = [0u8; 3];
+        let mut dst = [0u16; 2];
+        assert!((&data[..]).read_into::<BigEndian>(&mut dst).is_err());
+    }
+}
+
+/// Byte order that knows where to place a variable number of bytes (1 to 8)
+/// within a fixed 8-byte buffer.
+///
+/// This is used by the `read_uint`/`read_int`/`write_uint`/`write_int`
+/// methods to decode and encode integers that do not fill a whole number of
+/// power-of-two bytes, such as the 3-byte offsets or 5/6/7-byte counters
+/// found in some binary formats.
+pub trait VarByteOrder {
+    /// Returns the offset at which `nbytes` bytes should be placed within an
+    /// 8-byte buffer so that the remaining, zeroed bytes extend it to a full
+    /// `u64` in this byte order.
+    fn offset(nbytes: usize) -> usize;
+}
+
+impl VarByteOrder for LittleEndian {
+    #[inline]
+    fn offset(_nbytes: usize) -> usize { 0 }
+}
+
+impl VarByteOrder for BigEndian {
+    #[inline]
+    fn offset(nbytes: usize) -> usize { 8 - nbytes }
+}
+
+fn check_nbytes(nbytes: usize) -> io::Result<()> {
+    if nbytes < 1 || nbytes > 8 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nbytes must be between 1 and 8"
+        ))
+    }
+    Ok(())
+}
+
+/// Writes the low `nbytes` bytes of `n` in byte order `B`, assuming
+/// `1 <= nbytes <= 8` has already been validated.
+fn write_raw_uint<W, B>(w: &mut W, n: u64, nbytes: usize) -> io::Result<()>
+    where W: io::Write + ?Sized, B: ByteOrder<u64, Buffer = [u8; 8]> + VarByteOrder
+{
+    let buf = B::into_bytes(n);
+    let offset = B::offset(nbytes);
+    w.write_all(&buf[offset..offset + nbytes])
+}
+
+/// Extension trait for `io::Read` that allows to read variable-length
+/// integers of 1 to 8 bytes from it.
+pub trait ReadUintExt: io::Read {
+    /// Reads `nbytes` (1 to 8) bytes and interprets them as an unsigned
+    /// integer in byte order `B`.
+    fn read_uint<B>(&mut self, nbytes: usize) -> io::Result<u64>
+        where B: ByteOrder<u64, Buffer = [u8; 8]> + VarByteOrder
+    {
+        try!(check_nbytes(nbytes));
+        let mut buf = [0u8; 8];
+        let offset = B::offset(nbytes);
+        try!(self.read_exact(&mut buf[offset..offset + nbytes]));
+        Ok(B::from_bytes(buf))
+    }
+
+    /// Reads `nbytes` (1 to 8) bytes and interprets them as a sign-extended
+    /// signed integer in byte order `B`.
+    fn read_int<B>(&mut self, nbytes: usize) -> io::Result<i64>
+        where B: ByteOrder<u64, Buffer = [u8; 8]> + VarByteOrder
+    {
+        let val = try!(self.read_uint::<B>(nbytes));
+        let shift = (8 - nbytes) * 8;
+        Ok(((val << shift) as i64) >> shift)
+    }
+}
+
+impl<R: io::Read> ReadUintExt for R {}
+
+/// Extension trait for `io::Write` that allows to write variable-length
+/// integers of 1 to 8 bytes to it.
+pub trait WriteUintExt: io::Write {
+    /// Writes the low `nbytes` (1 to 8) bytes of `n` in byte order `B`.
+    ///
+    /// Debug builds assert that `n` actually fits in `nbytes` bytes; in
+    /// release builds a value that doesn't fit is silently truncated.
+    fn write_uint<B>(&mut self, n: u64, nbytes: usize) -> io::Result<()>
+        where B: ByteOrder<u64, Buffer = [u8; 8]> + VarByteOrder
+    {
+        try!(check_nbytes(nbytes));
+        debug_assert!(
+            nbytes == 8 || n >> (nbytes * 8) == 0,
+            "n does not fit in {} bytes", nbytes
+        );
+        write_raw_uint::<Self, B>(self, n, nbytes)
+    }
+
+    /// Writes the low `nbytes` (1 to 8) bytes of the two's-complement
+    /// representation of `n` in byte order `B`.
+    ///
+    /// Debug builds assert that `n` actually fits in `nbytes` bytes; in
+    /// release builds a value that doesn't fit is silently truncated.
+    fn write_int<B>(&mut self, n: i64, nbytes: usize) -> io::Result<()>
+        where B: ByteOrder<u64, Buffer = [u8; 8]> + VarByteOrder
+    {
+        try!(check_nbytes(nbytes));
+        let shift = (8 - nbytes) * 8;
+        debug_assert!(
+            nbytes == 8 || (n << shift) >> shift == n,
+            "n does not fit in {} bytes", nbytes
+        );
+        write_raw_uint::<Self, B>(self, n as u64, nbytes)
+    }
+}
+
+#[cfg(test)]
+mod var_int_tests {
+    use std::io;
+    use {BigEndian, LittleEndian, ReadUintExt, WriteUintExt};
+
+    /// A reader that only ever yields a single byte per `read` call, to
+    /// exercise the same short-read behavior a pipe or socket can produce.
+    struct OneByteAtATime<'a>(&'a [u8]);
+
+    impl<'a> io::Read for OneByteAtATime<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0)
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn read_uint_rejects_out_of_range_nbytes() {
+        let mut data: &[u8] = &[1, 2, 3];
+        assert!(data.read_uint::<LittleEndian>(0).is_err());
+        let mut data: &[u8] = &[1, 2, 3];
+        assert!(data.read_uint::<LittleEndian>(9).is_err());
+    }
+
+    #[test]
+    fn read_uint_round_trips_every_width() {
+        for nbytes in 1..9 {
+            let mut buf = Vec::new();
+            buf.write_uint::<BigEndian>(0x0102_0304_0506_0708u64 >> (8 * (8 - nbytes)), nbytes).unwrap();
+            let got = (&buf[..]).read_uint::<BigEndian>(nbytes).unwrap();
+            let expected = 0x0102_0304_0506_0708u64 >> (8 * (8 - nbytes));
+            assert_eq!(got, expected, "nbytes = {}", nbytes);
+        }
+    }
+
+    #[test]
+    fn read_int_sign_extends_at_every_width() {
+        for nbytes in 1..9 {
+            let mut buf = Vec::new();
+            buf.write_int::<BigEndian>(-1, nbytes).unwrap();
+            assert_eq!((&buf[..]).read_int::<BigEndian>(nbytes).unwrap(), -1, "nbytes = {}", nbytes);
+
+            let mut buf = Vec::new();
+            buf.write_int::<BigEndian>(1, nbytes).unwrap();
+            assert_eq!((&buf[..]).read_int::<BigEndian>(nbytes).unwrap(), 1, "nbytes = {}", nbytes);
+        }
+    }
+
+    #[test]
+    fn read_uint_uses_read_exact_across_short_reads() {
+        let data = [0x01u8, 0x02, 0x03];
+        let mut reader = OneByteAtATime(&data);
+        let val = reader.read_uint::<BigEndian>(3).unwrap();
+        assert_eq!(val, 0x0102_03);
+    }
+}
+
+impl<W: io::Write> WriteUintExt for W {}
\ No newline at end of file
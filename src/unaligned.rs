@@ -0,0 +1,98 @@
+//! Fixed-layout, byte-order-tagged numeric wrapper types.
+//!
+//! These types mirror a multi-byte field inside a packed, unaligned
+//! on-disk or on-wire layout: each is a `#[repr(transparent)]` wrapper
+//! around a `[u8; N]` with alignment 1, so it can be embedded directly in
+//! a `#[repr(C, packed)]` struct that overlays a network packet or file
+//! header, and read or written without manual offset arithmetic.
+
+use std::marker::PhantomData;
+
+use super::ByteOrder;
+
+macro_rules! def_unaligned {
+    ($name:ident, $val:ty, $bytes:expr) => {
+        #[repr(transparent)]
+        #[derive(Clone, Copy)]
+        pub struct $name<B> {
+            bytes: [u8; $bytes],
+            _order: PhantomData<B>,
+        }
+
+        impl<B: ByteOrder<$val, Buffer = [u8; $bytes]>> $name<B> {
+            /// Creates a new wrapped value, encoding `value` into byte order `B`.
+            pub fn new(value: $val) -> Self {
+                $name { bytes: B::into_bytes(value), _order: PhantomData }
+            }
+
+            /// Decodes the wrapped bytes into a value in native byte order.
+            pub fn get(&self) -> $val {
+                B::from_bytes(self.bytes)
+            }
+
+            /// Overwrites the wrapped bytes, encoding `value` into byte order `B`.
+            pub fn set(&mut self, value: $val) {
+                self.bytes = B::into_bytes(value);
+            }
+        }
+
+        impl<B: ByteOrder<$val, Buffer = [u8; $bytes]>> From<$val> for $name<B> {
+            fn from(value: $val) -> Self {
+                $name::new(value)
+            }
+        }
+
+        impl<B: ByteOrder<$val, Buffer = [u8; $bytes]>> From<$name<B>> for $val {
+            fn from(wrapped: $name<B>) -> $val {
+                wrapped.get()
+            }
+        }
+    }
+}
+
+def_unaligned!(U16, u16, 2);
+def_unaligned!(U32, u32, 4);
+def_unaligned!(U64, u64, 8);
+def_unaligned!(I16, i16, 2);
+def_unaligned!(I32, i32, 4);
+def_unaligned!(I64, i64, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::{I16, I32, I64, U16, U32, U64};
+    use {BigEndian, LittleEndian};
+    use std::mem;
+
+    #[test]
+    fn get_round_trips_new_in_both_byte_orders() {
+        assert_eq!(U16::<LittleEndian>::new(0x0102).get(), 0x0102);
+        assert_eq!(U16::<BigEndian>::new(0x0102).get(), 0x0102);
+        assert_eq!(U32::<LittleEndian>::new(0x0102_0304).get(), 0x0102_0304);
+        assert_eq!(U32::<BigEndian>::new(0x0102_0304).get(), 0x0102_0304);
+        assert_eq!(U64::<BigEndian>::new(0x0102_0304_0506_0708).get(), 0x0102_0304_0506_0708);
+        assert_eq!(I16::<BigEndian>::new(-1).get(), -1);
+        assert_eq!(I32::<BigEndian>::new(-1).get(), -1);
+        assert_eq!(I64::<BigEndian>::new(-1).get(), -1);
+    }
+
+    #[test]
+    fn set_overwrites_the_wrapped_bytes() {
+        let mut x = U32::<BigEndian>::new(1);
+        x.set(0xdead_beef);
+        assert_eq!(x.get(), 0xdead_beef);
+    }
+
+    #[test]
+    fn byte_order_is_reflected_in_the_underlying_bytes() {
+        let le = U16::<LittleEndian>::new(0x0102);
+        let be = U16::<BigEndian>::new(0x0102);
+        assert_eq!(unsafe { mem::transmute_copy::<_, [u8; 2]>(&le) }, [0x02, 0x01]);
+        assert_eq!(unsafe { mem::transmute_copy::<_, [u8; 2]>(&be) }, [0x01, 0x02]);
+    }
+
+    #[test]
+    fn is_repr_transparent_and_alignment_one() {
+        assert_eq!(mem::size_of::<U32<LittleEndian>>(), 4);
+        assert_eq!(mem::align_of::<U32<LittleEndian>>(), 1);
+    }
+}